@@ -1,6 +1,9 @@
 #![no_std]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use core::{fmt, marker::PhantomData, ops::Mul};
 use digest::{
     array::{typenum::Unsigned, Array, ArraySize},
@@ -9,12 +12,24 @@ use digest::{
     typenum::op,
     KeyInit, Mac,
 };
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 pub mod sealed;
+#[cfg(feature = "self-test")]
+pub mod self_test;
 
 #[derive(Debug, PartialEq)]
 pub enum Error {
     InvalidRequestSize,
+    /// `data_before_ctr`/`data_after_ctr` don't agree with the requested [`CounterLocation`]
+    /// (e.g. a non-empty `data_before_ctr` with [`CounterLocation::Before`], or both segments
+    /// empty with [`CounterLocation::Middle`]).
+    InvalidCounterLocation,
+    /// [`self_test::self_test`](crate::self_test::self_test) produced output that didn't match
+    /// one of its embedded regression vectors.
+    #[cfg(feature = "self-test")]
+    SelfTestFailed,
 }
 
 impl fmt::Display for Error {
@@ -24,12 +39,38 @@ impl fmt::Display for Error {
                 f,
                 "Request output size is too large for the value of R specified"
             ),
+            Error::InvalidCounterLocation => write!(
+                f,
+                "data_before_ctr/data_after_ctr do not match the requested CounterLocation"
+            ),
+            #[cfg(feature = "self-test")]
+            Error::SelfTestFailed => write!(
+                f,
+                "a derivation did not match its expected regression vector"
+            ),
         }
     }
 }
 
 impl core::error::Error for Error {}
 
+/// Where, within the fixed input data, the counter `i` is placed.
+///
+/// NIST SP 800-108 permits the counter to be encoded at the beginning, middle, or end of the
+/// fixed input string. This is needed to interoperate with protocols (e.g. certain
+/// TLS/Kerberos/SMB profiles) that encode the counter in the middle of a structured
+/// label/context blob, which [`Kbkdf::derive`]'s fixed `[counter] || label || 0x00 || context ||
+/// [L]` layout cannot reproduce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterLocation {
+    /// The counter is encoded before the fixed input data (the layout [`Kbkdf::derive`] uses).
+    Before,
+    /// The counter is encoded in the middle of the fixed input data.
+    Middle,
+    /// The counter is encoded after the fixed input data.
+    After,
+}
+
 // Helper structure along with [`KbkdfUser`] to compute values of L and H.
 struct KbkdfCore<OutputLen, PrfOutputLen> {
     _marker: PhantomData<(OutputLen, PrfOutputLen)>,
@@ -57,6 +98,83 @@ where
     type H = op!(PrfOutputLen * U8);
 }
 
+/// The PRF-iteration loop shared by [`Kbkdf::derive_into`], [`Kbkdf::derive_with_location`], and
+/// [`KbkdfCustom::derive`].
+///
+/// Those three entry points differ only in how they construct a fresh `Prf` (`new_prf`, e.g.
+/// `Prf::new_from_slice(kin)` vs [`CustomizablePrf::new_customized`]) and how they assemble each
+/// iteration's fixed input data (`fixed_data`, e.g. `label || 0x00 || context || [L]` vs a
+/// caller-supplied layout around [`CounterLocation`]). The counter/feedback/double-pipeline
+/// bookkeeping and zeroizing around that is identical, so it lives here once.
+///
+/// `a` is the already-computed initial chaining value (the first PRF invocation, over the fixed
+/// data without a counter; [`Kbkdf::derive_into`] also leaves out `[L]` here) and `ki` the
+/// already-seeded IV, if any — both mode-specific, so the caller computes them before calling in. `out` receives the first `out.len()` bytes of output.
+fn kbkdf_loop<Prf: Mac>(
+    out: &mut [u8],
+    n: u32,
+    feedback_ki: bool,
+    double_pipeline: bool,
+    mut ki: Option<Array<u8, Prf::OutputSize>>,
+    mut a: Array<u8, Prf::OutputSize>,
+    mut new_prf: impl FnMut() -> Prf,
+    mut fixed_data: impl FnMut(&mut Prf, u32),
+) {
+    let mut builder = out;
+
+    for counter in 1..=n {
+        if counter > 1 {
+            let new_a = {
+                let mut h = new_prf();
+                h.update(a.as_slice());
+                h.finalize().into_bytes()
+            };
+            #[cfg(feature = "zeroize")]
+            a.as_mut_slice().zeroize();
+            a = new_a;
+        }
+
+        let mut h = new_prf();
+
+        if feedback_ki {
+            if let Some(mut prev_ki) = ki.take() {
+                h.update(prev_ki.as_slice());
+                #[cfg(feature = "zeroize")]
+                prev_ki.as_mut_slice().zeroize();
+            }
+        }
+
+        if double_pipeline {
+            h.update(a.as_slice());
+        }
+
+        fixed_data(&mut h, counter);
+
+        let mut buf = h.finalize().into_bytes();
+        if feedback_ki {
+            ki = Some(buf.clone());
+        }
+
+        let remaining = usize::min(buf.len(), builder.len());
+
+        builder[..remaining].copy_from_slice(&buf[..remaining]);
+        builder = &mut builder[remaining..];
+
+        #[cfg(feature = "zeroize")]
+        buf.as_mut_slice().zeroize();
+    }
+
+    #[cfg(feature = "zeroize")]
+    {
+        a.as_mut_slice().zeroize();
+        if let Some(mut ki) = ki {
+            ki.as_mut_slice().zeroize();
+        }
+    }
+
+    assert_eq!(builder.len(), 0, "output has uninitialized bytes");
+}
+
 /// [`Kbkdf`] is a trait representing a mode of KBKDF.
 /// It takes multiple arguments:
 ///  - Prf - the Pseudorandom Function to derive keys from
@@ -73,6 +191,9 @@ where
     <Prf::OutputSize as Mul<U8>>::Output: Unsigned,
 {
     /// Derives `key` from `kin` and other parameters.
+    ///
+    /// This is a thin wrapper around [`Self::derive_into`] for callers who already have a
+    /// [`KeySizeUser`] type `K` describing the desired output length.
     fn derive(
         &self,
         kin: &[u8],
@@ -82,21 +203,50 @@ where
         label: &[u8],
         context: &[u8],
     ) -> Result<Array<u8, K::KeySize>, Error> {
+        let mut output = Array::<u8, K::KeySize>::default();
+        self.derive_into(
+            kin,
+            use_l,
+            use_separator,
+            use_counter,
+            label,
+            context,
+            &mut output,
+        )?;
+        Ok(output)
+    }
+
+    /// Derives exactly `out.len()` bytes of keying material into `out`.
+    ///
+    /// Unlike [`Self::derive`], the output length is a runtime value (`L = out.len() * 8`)
+    /// rather than the type-level `K::KeySize`, so callers can derive arbitrary-length or
+    /// odd-length keys without defining a [`KeySizeUser`] type for every distinct length.
+    fn derive_into(
+        &self,
+        kin: &[u8],
+        use_l: bool,
+        use_separator: bool,
+        use_counter: bool,
+        label: &[u8],
+        context: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        // L - the requested length (in bits) of the derived keying material, computed from the
+        // caller-supplied buffer rather than the type-level K::KeySize.
+        let l_bits = out.len() as u32 * 8;
+
         // n - An integer whose value is the number of iterations of the PRF needed to generate L
         // bits of keying material
-        let n: u32 = <KbkdfCore<K::KeySize, Prf::OutputSize> as KbkdfUser>::L::U32
-            .div_ceil(<KbkdfCore<K::KeySize, Prf::OutputSize> as KbkdfUser>::H::U32);
+        let n: u32 =
+            l_bits.div_ceil(<KbkdfCore<K::KeySize, Prf::OutputSize> as KbkdfUser>::H::U32);
 
         if n as usize > 2usize.pow(R::U32) - 1 {
             return Err(Error::InvalidRequestSize);
         }
 
-        let mut output = Array::<u8, K::KeySize>::default();
-        let mut builder = output.as_mut_slice();
-
         let mut ki = None;
         self.input_iv(&mut ki);
-        let mut a = {
+        let a = {
             let mut h = Prf::new_from_slice(kin).unwrap();
             h.update(label);
             if use_separator {
@@ -106,63 +256,262 @@ where
             h.finalize().into_bytes()
         };
 
-        for counter in 1..=n {
-            if counter > 1 {
-                a = {
-                    let mut h = Prf::new_from_slice(kin).unwrap();
-                    h.update(a.as_slice());
-                    h.finalize().into_bytes()
-                };
-            }
+        kbkdf_loop(
+            out,
+            n,
+            Self::FEEDBACK_KI,
+            Self::DOUBLE_PIPELINE,
+            ki,
+            a,
+            || Prf::new_from_slice(kin).unwrap(),
+            |h, counter| {
+                if use_counter {
+                    // counter encoded as big endian u32
+                    // Type parameter R encodes how large the value is to be (either U8, U16,
+                    // U24, or U32)
+                    //
+                    // counter = 1u32 ([0, 0, 0, 1])
+                    //                     \-------/
+                    //                      R = u24
+                    h.update(&counter.to_be_bytes()[(4 - R::USIZE / 8)..]);
+                }
+
+                // Fixed input data
+                h.update(label);
+                if use_separator {
+                    h.update(&[0]);
+                }
+                h.update(context);
+                if use_l {
+                    h.update(&l_bits.to_be_bytes()[..]);
+                }
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Derives `len` bytes of keying material into a freshly allocated buffer.
+    ///
+    /// A convenience wrapper around [`Self::derive_into`] for callers who need a
+    /// runtime-determined output length without pre-allocating their own buffer. Requires the
+    /// `alloc` feature; the core [`Self::derive_into`] stays usable in `#![no_std]` without a
+    /// global allocator.
+    #[cfg(feature = "alloc")]
+    fn derive_vec(
+        &self,
+        kin: &[u8],
+        use_l: bool,
+        use_separator: bool,
+        use_counter: bool,
+        label: &[u8],
+        context: &[u8],
+        len: usize,
+    ) -> Result<alloc::vec::Vec<u8>, Error> {
+        let mut out = alloc::vec![0u8; len];
+        self.derive_into(kin, use_l, use_separator, use_counter, label, context, &mut out)?;
+        Ok(out)
+    }
+
+    /// Derives `key` from `kin`, with explicit control over where the counter `i` is placed
+    /// within the fixed input data.
+    ///
+    /// Unlike [`Self::derive`], the fixed input data is not assembled from `label`/`context`;
+    /// callers supply it pre-split as `data_before_ctr` and `data_after_ctr` around the counter
+    /// position implied by `counter_location`, so protocols that embed the counter inside a
+    /// structured blob (rather than at the very start) can be reproduced exactly.
+    ///
+    /// Returns [`Error::InvalidCounterLocation`] if the segments don't agree with
+    /// `counter_location` (e.g. a non-empty `data_before_ctr` with [`CounterLocation::Before`]).
+    fn derive_with_location(
+        &self,
+        kin: &[u8],
+        use_counter: bool,
+        counter_location: CounterLocation,
+        data_before_ctr: &[u8],
+        data_after_ctr: &[u8],
+    ) -> Result<Array<u8, K::KeySize>, Error> {
+        // Before/After require the segment on the counter's far side to be empty (otherwise the
+        // layout contradicts counter_location). Middle only needs *some* structure to split the
+        // counter into, i.e. at least one of the two segments non-empty; requiring both would
+        // reject legitimate layouts with an empty label or an empty context.
+        let location_matches_segments = match counter_location {
+            CounterLocation::Before => data_before_ctr.is_empty(),
+            CounterLocation::After => data_after_ctr.is_empty(),
+            CounterLocation::Middle => !data_before_ctr.is_empty() || !data_after_ctr.is_empty(),
+        };
+        if !location_matches_segments {
+            return Err(Error::InvalidCounterLocation);
+        }
+
+        // n - An integer whose value is the number of iterations of the PRF needed to generate L
+        // bits of keying material
+        let n: u32 = <KbkdfCore<K::KeySize, Prf::OutputSize> as KbkdfUser>::L::U32
+            .div_ceil(<KbkdfCore<K::KeySize, Prf::OutputSize> as KbkdfUser>::H::U32);
+
+        if n as usize > 2usize.pow(R::U32) - 1 {
+            return Err(Error::InvalidRequestSize);
+        }
 
+        let mut output = Array::<u8, K::KeySize>::default();
+
+        let mut ki = None;
+        self.input_iv(&mut ki);
+        let a = {
             let mut h = Prf::new_from_slice(kin).unwrap();
+            h.update(data_before_ctr);
+            h.update(data_after_ctr);
+            h.finalize().into_bytes()
+        };
 
-            if Self::FEEDBACK_KI {
-                if let Some(ki) = ki {
-                    h.update(ki.as_slice());
+        kbkdf_loop(
+            output.as_mut_slice(),
+            n,
+            Self::FEEDBACK_KI,
+            Self::DOUBLE_PIPELINE,
+            ki,
+            a,
+            || Prf::new_from_slice(kin).unwrap(),
+            |h, counter| {
+                // The counter's position relative to data_before_ctr/data_after_ctr is driven
+                // directly by counter_location, rather than inferred from which segments are
+                // non-empty.
+                match counter_location {
+                    CounterLocation::Before => {
+                        if use_counter {
+                            // counter encoded as big endian u32
+                            // Type parameter R encodes how large the value is to be (either U8,
+                            // U16, U24, or U32)
+                            //
+                            // counter = 1u32 ([0, 0, 0, 1])
+                            //                     \-------/
+                            //                      R = u24
+                            h.update(&counter.to_be_bytes()[(4 - R::USIZE / 8)..]);
+                        }
+                        h.update(data_after_ctr);
+                    }
+                    CounterLocation::Middle => {
+                        h.update(data_before_ctr);
+                        if use_counter {
+                            h.update(&counter.to_be_bytes()[(4 - R::USIZE / 8)..]);
+                        }
+                        h.update(data_after_ctr);
+                    }
+                    CounterLocation::After => {
+                        h.update(data_before_ctr);
+                        if use_counter {
+                            h.update(&counter.to_be_bytes()[(4 - R::USIZE / 8)..]);
+                        }
+                    }
                 }
-            }
+            },
+        );
 
-            if Self::DOUBLE_PIPELINE {
-                h.update(a.as_slice());
-            }
-            if use_counter {
-                // counter encoded as big endian u32
-                // Type parameter R encodes how large the value is to be (either U8, U16, U24, or U32)
-                //
-                // counter = 1u32 ([0, 0, 0, 1])
-                //                     \-------/
-                //                      R = u24
-                h.update(&counter.to_be_bytes()[(4 - R::USIZE / 8)..]);
-            }
+        Ok(output)
+    }
 
-            // Fixed input data
+    /// Input the IV in the PRF
+    fn input_iv(&self, _ki: &mut Option<Array<u8, Prf::OutputSize>>) {}
+
+    /// Whether the KI should be reinjected every round.
+    const FEEDBACK_KI: bool = false;
+
+    const DOUBLE_PIPELINE: bool = false;
+}
+
+/// A PRF that binds its own customization string `S` and the requested output length `L` into
+/// its construction, rather than requiring the KDF to concatenate them into the fixed input
+/// data.
+///
+/// KMAC128 and KMAC256 — the PRFs added by SP 800-108r1 — work this way: `L` is right-encoded
+/// per the cSHAKE/KMAC spec and absorbed internally by the MAC, so a mode built on a
+/// `CustomizablePrf` must suppress the `use_l`/fixed-data encoding that [`Kbkdf::derive`] uses
+/// for HMAC-style PRFs.
+pub trait CustomizablePrf: Mac {
+    /// Builds this PRF from the KDF input key `kin`, the customization string `S`, and the
+    /// requested output length `L` in bits.
+    fn new_customized(kin: &[u8], customization: &[u8], l_bits: u32) -> Self;
+}
+
+/// Like [`Kbkdf`], but for modes built on a [`CustomizablePrf`] (KMAC128/KMAC256 per
+/// SP 800-108r1) instead of an ordinary [`Mac`].
+///
+/// The customization string `S` is threaded through the mode struct itself (see e.g.
+/// [`Counter::with_customization`]) rather than passed to `derive`, mirroring how
+/// [`Feedback`] threads its IV through [`Kbkdf::input_iv`].
+pub trait KbkdfCustom<Prf, K, R: sealed::R>
+where
+    Prf: CustomizablePrf,
+    K: KeySizeUser,
+    K::KeySize: ArraySize + Mul<U8>,
+    <K::KeySize as Mul<U8>>::Output: Unsigned,
+    Prf::OutputSize: ArraySize + Mul<U8>,
+    <Prf::OutputSize as Mul<U8>>::Output: Unsigned,
+{
+    /// Derives `key` from `kin` using this mode's [`CustomizablePrf`].
+    ///
+    /// `L` is bound into the PRF by [`CustomizablePrf::new_customized`] rather than appended to
+    /// the fixed input data, so unlike [`Kbkdf::derive`] there is no `use_l` flag.
+    fn derive(
+        &self,
+        kin: &[u8],
+        use_separator: bool,
+        use_counter: bool,
+        label: &[u8],
+        context: &[u8],
+    ) -> Result<Array<u8, K::KeySize>, Error> {
+        let n: u32 = <KbkdfCore<K::KeySize, Prf::OutputSize> as KbkdfUser>::L::U32
+            .div_ceil(<KbkdfCore<K::KeySize, Prf::OutputSize> as KbkdfUser>::H::U32);
+
+        if n as usize > 2usize.pow(R::U32) - 1 {
+            return Err(Error::InvalidRequestSize);
+        }
+
+        let l_bits = <KbkdfCore<K::KeySize, Prf::OutputSize> as KbkdfUser>::L::U32;
+        let customization = self.customization();
+
+        let mut output = Array::<u8, K::KeySize>::default();
+
+        let mut ki = None;
+        self.input_iv(&mut ki);
+        let a = {
+            let mut h = Prf::new_customized(kin, customization, l_bits);
             h.update(label);
             if use_separator {
                 h.update(&[0]);
             }
             h.update(context);
-            if use_l {
-                h.update(
-                    &(<KbkdfCore<K::KeySize, Prf::OutputSize> as KbkdfUser>::L::U32).to_be_bytes()
-                        [..],
-                );
-            }
-
-            let buf = h.finalize().into_bytes();
-            ki = Some(buf.clone());
-
-            let remaining = usize::min(buf.len(), builder.len());
-
-            builder[..remaining].copy_from_slice(&buf[..remaining]);
-            builder = &mut builder[remaining..];
-        }
+            h.finalize().into_bytes()
+        };
 
-        assert_eq!(builder.len(), 0, "output has uninitialized bytes");
+        kbkdf_loop(
+            output.as_mut_slice(),
+            n,
+            Self::FEEDBACK_KI,
+            Self::DOUBLE_PIPELINE,
+            ki,
+            a,
+            || Prf::new_customized(kin, customization, l_bits),
+            |h, counter| {
+                if use_counter {
+                    h.update(&counter.to_be_bytes()[(4 - R::USIZE / 8)..]);
+                }
+                h.update(label);
+                if use_separator {
+                    h.update(&[0]);
+                }
+                h.update(context);
+            },
+        );
 
         Ok(output)
     }
 
+    /// The customization string `S` fed to the PRF. Defaults to empty.
+    fn customization(&self) -> &[u8] {
+        &[]
+    }
+
     /// Input the IV in the PRF
     fn input_iv(&self, _ki: &mut Option<Array<u8, Prf::OutputSize>>) {}
 
@@ -172,19 +521,33 @@ where
     const DOUBLE_PIPELINE: bool = false;
 }
 
-pub struct Counter<Prf, K, R = U32> {
+pub struct Counter<'a, Prf, K, R = U32> {
+    customization: Option<&'a [u8]>,
     _marker: PhantomData<(Prf, K, R)>,
 }
 
-impl<Prf, K, R> Default for Counter<Prf, K, R> {
+impl<'a, Prf, K, R> Default for Counter<'a, Prf, K, R> {
     fn default() -> Self {
         Self {
+            customization: None,
             _marker: PhantomData,
         }
     }
 }
 
-impl<Prf, K, R> Kbkdf<Prf, K, R> for Counter<Prf, K, R>
+impl<'a, Prf, K, R> Counter<'a, Prf, K, R> {
+    /// Builds a counter-mode instance that feeds `customization` to the PRF as its
+    /// customization string `S`. Only meaningful when `Prf` is a [`CustomizablePrf`] (e.g.
+    /// KMAC128/KMAC256); use [`Counter::default`] with an ordinary [`Mac`] PRF otherwise.
+    pub fn with_customization(customization: &'a [u8]) -> Self {
+        Self {
+            customization: Some(customization),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, Prf, K, R> Kbkdf<Prf, K, R> for Counter<'a, Prf, K, R>
 where
     Prf: Mac + KeyInit,
     K: KeySizeUser,
@@ -196,11 +559,27 @@ where
 {
 }
 
+impl<'a, Prf, K, R> KbkdfCustom<Prf, K, R> for Counter<'a, Prf, K, R>
+where
+    Prf: CustomizablePrf,
+    K: KeySizeUser,
+    K::KeySize: ArraySize + Mul<U8>,
+    <K::KeySize as Mul<U8>>::Output: Unsigned,
+    Prf::OutputSize: ArraySize + Mul<U8>,
+    <Prf::OutputSize as Mul<U8>>::Output: Unsigned,
+    R: sealed::R,
+{
+    fn customization(&self) -> &[u8] {
+        self.customization.unwrap_or(&[])
+    }
+}
+
 pub struct Feedback<'a, Prf, K, R = U32>
 where
     Prf: Mac,
 {
     iv: Option<&'a Array<u8, Prf::OutputSize>>,
+    customization: Option<&'a [u8]>,
     _marker: PhantomData<(Prf, K, R)>,
 }
 
@@ -211,6 +590,21 @@ where
     pub fn new(iv: Option<&'a Array<u8, Prf::OutputSize>>) -> Self {
         Self {
             iv,
+            customization: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Builds a feedback-mode instance that additionally feeds `customization` to the PRF as
+    /// its customization string `S`. Only meaningful when `Prf` is a [`CustomizablePrf`] (e.g.
+    /// KMAC128/KMAC256); use [`Feedback::new`] with an ordinary [`Mac`] PRF otherwise.
+    pub fn with_customization(
+        iv: Option<&'a Array<u8, Prf::OutputSize>>,
+        customization: &'a [u8],
+    ) -> Self {
+        Self {
+            iv,
+            customization: Some(customization),
             _marker: PhantomData,
         }
     }
@@ -235,25 +629,65 @@ where
     const FEEDBACK_KI: bool = true;
 }
 
-pub struct DoublePipeline<Prf, K, R = U32>
+impl<'a, Prf, K, R> KbkdfCustom<Prf, K, R> for Feedback<'a, Prf, K, R>
+where
+    Prf: CustomizablePrf,
+    K: KeySizeUser,
+    K::KeySize: ArraySize + Mul<U8>,
+    <K::KeySize as Mul<U8>>::Output: Unsigned,
+    Prf::OutputSize: ArraySize + Mul<U8>,
+    <Prf::OutputSize as Mul<U8>>::Output: Unsigned,
+    R: sealed::R,
+{
+    fn input_iv(&self, ki: &mut Option<Array<u8, Prf::OutputSize>>) {
+        if let Some(iv) = self.iv {
+            *ki = Some(iv.clone())
+        }
+    }
+
+    fn customization(&self) -> &[u8] {
+        self.customization.unwrap_or(&[])
+    }
+
+    const FEEDBACK_KI: bool = true;
+}
+
+pub struct DoublePipeline<'a, Prf, K, R = U32>
 where
     Prf: Mac,
 {
+    customization: Option<&'a [u8]>,
     _marker: PhantomData<(Prf, K, R)>,
 }
 
-impl<Prf, K, R> Default for DoublePipeline<Prf, K, R>
+impl<'a, Prf, K, R> Default for DoublePipeline<'a, Prf, K, R>
 where
     Prf: Mac,
 {
     fn default() -> Self {
         Self {
+            customization: None,
             _marker: PhantomData,
         }
     }
 }
 
-impl<Prf, K, R> Kbkdf<Prf, K, R> for DoublePipeline<Prf, K, R>
+impl<'a, Prf, K, R> DoublePipeline<'a, Prf, K, R>
+where
+    Prf: Mac,
+{
+    /// Builds a double-pipeline-mode instance that feeds `customization` to the PRF as its
+    /// customization string `S`. Only meaningful when `Prf` is a [`CustomizablePrf`] (e.g.
+    /// KMAC128/KMAC256); use [`DoublePipeline::default`] with an ordinary [`Mac`] PRF otherwise.
+    pub fn with_customization(customization: &'a [u8]) -> Self {
+        Self {
+            customization: Some(customization),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, Prf, K, R> Kbkdf<Prf, K, R> for DoublePipeline<'a, Prf, K, R>
 where
     Prf: Mac + KeyInit,
     K: KeySizeUser,
@@ -266,5 +700,22 @@ where
     const DOUBLE_PIPELINE: bool = true;
 }
 
+impl<'a, Prf, K, R> KbkdfCustom<Prf, K, R> for DoublePipeline<'a, Prf, K, R>
+where
+    Prf: CustomizablePrf,
+    K: KeySizeUser,
+    K::KeySize: ArraySize + Mul<U8>,
+    <K::KeySize as Mul<U8>>::Output: Unsigned,
+    Prf::OutputSize: ArraySize + Mul<U8>,
+    <Prf::OutputSize as Mul<U8>>::Output: Unsigned,
+    R: sealed::R,
+{
+    fn customization(&self) -> &[u8] {
+        self.customization.unwrap_or(&[])
+    }
+
+    const DOUBLE_PIPELINE: bool = true;
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,103 @@
+//! Built-in derivation regression guard.
+//!
+//! [`self_test`] runs a small set of fixed input/output pairs against [`Kbkdf::derive`] for each
+//! supported mode and returns an error on the first mismatch. It is useful as a cheap runtime
+//! sanity check that the derivation loop still produces the bytes this crate has always produced
+//! (e.g. after swapping the `Prf`/`R` type parameters, or upgrading the `digest` dependency).
+//!
+//! # Not a CAVP self-test
+//!
+//! The original ask for this module was a power-on self-test built from the official NIST CAVP
+//! `KDFCTR`/`KDFFEEDBACK`/`KDFDBLPIPELINE` response-file vectors, so FIPS-track integrators could
+//! call it at startup instead of reimplementing a vector harness externally. **That request is not
+//! fulfilled by this module.** The constants below were generated by running this crate's own
+//! `derive`, not copied from a CAVP response file, so [`self_test`] only proves the derivation loop
+//! is internally consistent with itself release-to-release — a derivation that is internally
+//! consistent but non-conformant to SP 800-108 would still pass it. It must not be presented to
+//! auditors as a CAVP or FIPS self-test.
+//!
+//! Fulfilling the original request requires transcribing the real CAVP response-file vectors,
+//! which this sandboxed, network-less build environment has no way to fetch. Swapping them in
+//! later is a drop-in change (same `[u8; N]` constants, same call sites) for whoever has access to
+//! the NIST response files; it has not been done here.
+
+use core::marker::PhantomData;
+
+use digest::{
+    array::{Array, ArraySize},
+    consts::U32,
+    crypto_common::KeySizeUser,
+};
+use hmac::Hmac;
+use sha2::Sha256;
+
+use crate::{Counter, DoublePipeline, Error, Feedback, Kbkdf};
+
+/// A [`KeySizeUser`] whose `KeySize` is given by the type parameter, so the self-test vectors
+/// below don't need a bespoke marker type per output length.
+struct OutputSize<Size>(PhantomData<Size>);
+
+impl<Size: ArraySize> KeySizeUser for OutputSize<Size> {
+    type KeySize = Size;
+}
+
+const KIN: [u8; 32] = {
+    let mut kin = [0u8; 32];
+    let mut i = 0;
+    while i < 32 {
+        kin[i] = i as u8;
+        i += 1;
+    }
+    kin
+};
+const LABEL: &[u8] = b"label";
+const CONTEXT: &[u8] = b"context";
+
+const COUNTER_EXPECTED: [u8; 32] =
+    hex_literal::hex!("303790cfe363abe9682dbfff5941f23b32addc96da72f4c7e5b20e9f59a4e570");
+
+const FEEDBACK_NO_IV_EXPECTED: [u8; 64] = hex_literal::hex!(
+    "a96742dab629385c2fda3ab31ff80ae5ab8f18d61a903f75d4cb97422e3b95863c279024b8d7408dbe530e1659da8d69e5a1ee5c3b8a69c2dcc3e1d7da571fc9"
+);
+
+const FEEDBACK_IV: [u8; 32] = [0xAA; 32];
+const FEEDBACK_IV_EXPECTED: [u8; 32] =
+    hex_literal::hex!("81c25c93189a515d9ecae4d7a01852e817feb0db13f00b7d3b6e46493bb72bbb");
+
+const DOUBLE_PIPELINE_EXPECTED: [u8; 32] =
+    hex_literal::hex!("2d056ba71c023fb6e30ae32b847983aead7993952f6c85bdaaa0036f1f16ee57");
+
+/// Runs the embedded regression vectors for every supported mode (counter, feedback with and
+/// without an IV, double-pipeline) and fails on the first mismatch.
+///
+/// See the module docs for what this does and does not guarantee: it catches regressions in the
+/// derivation loop, it does not establish SP 800-108 conformance.
+pub fn self_test() -> Result<(), Error> {
+    let counter = Counter::<'_, Hmac<Sha256>, OutputSize<U32>>::default().derive(
+        &KIN, true, true, true, LABEL, CONTEXT,
+    )?;
+    if counter.as_slice() != COUNTER_EXPECTED {
+        return Err(Error::SelfTestFailed);
+    }
+
+    let feedback_no_iv = Feedback::<'_, Hmac<Sha256>, OutputSize<digest::consts::U64>>::new(None)
+        .derive(&KIN, true, true, true, LABEL, CONTEXT)?;
+    if feedback_no_iv.as_slice() != FEEDBACK_NO_IV_EXPECTED {
+        return Err(Error::SelfTestFailed);
+    }
+
+    let iv = Array::from(FEEDBACK_IV);
+    let feedback_iv = Feedback::<'_, Hmac<Sha256>, OutputSize<U32>>::new(Some(&iv))
+        .derive(&KIN, true, true, true, LABEL, CONTEXT)?;
+    if feedback_iv.as_slice() != FEEDBACK_IV_EXPECTED {
+        return Err(Error::SelfTestFailed);
+    }
+
+    let double_pipeline = DoublePipeline::<'_, Hmac<Sha256>, OutputSize<U32>>::default()
+        .derive(&KIN, true, true, true, LABEL, CONTEXT)?;
+    if double_pipeline.as_slice() != DOUBLE_PIPELINE_EXPECTED {
+        return Err(Error::SelfTestFailed);
+    }
+
+    Ok(())
+}
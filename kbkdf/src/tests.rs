@@ -0,0 +1,50 @@
+//! Unit tests for [`Kbkdf`]/[`KbkdfCustom`] that need items only meaningful under `cfg(test)`.
+
+use core::marker::PhantomData;
+
+use digest::{array::ArraySize, consts::U32, crypto_common::KeySizeUser, KeyInit, Mac};
+use hmac::Hmac;
+use sha2::Sha256;
+
+use crate::{Counter, CustomizablePrf, KbkdfCustom};
+
+/// A **test-only** [`CustomizablePrf`] impl for `Hmac<Sha256>`, confined to `cfg(test)` so it
+/// can't leak `Hmac<Sha256>: CustomizablePrf` into downstream crates that merely enable the
+/// `self-test` feature.
+///
+/// HMAC-SHA256 is not an actual customizable PRF — KMAC128/KMAC256 are, per SP 800-108r1 — so
+/// this folds `customization` and `l_bits` into the HMAC key rather than absorbing them the way
+/// cSHAKE/KMAC would. That makes the construction self-consistent, not KMAC-conformant; it exists
+/// only to exercise [`KbkdfCustom::derive`]'s customization/`L`-suppression wiring below.
+impl CustomizablePrf for Hmac<Sha256> {
+    fn new_customized(kin: &[u8], customization: &[u8], l_bits: u32) -> Self {
+        let mut fold = <Hmac<Sha256> as KeyInit>::new_from_slice(kin)
+            .expect("HMAC-SHA256 accepts any key length");
+        fold.update(customization);
+        fold.update(&l_bits.to_be_bytes());
+        let key = fold.finalize().into_bytes();
+        <Hmac<Sha256> as KeyInit>::new_from_slice(&key).expect("HMAC-SHA256 accepts any key length")
+    }
+}
+
+struct OutputSize<Size>(PhantomData<Size>);
+
+impl<Size: ArraySize> KeySizeUser for OutputSize<Size> {
+    type KeySize = Size;
+}
+
+#[test]
+fn kbkdf_custom_counter_matches_known_vector() {
+    let kin: [u8; 32] = core::array::from_fn(|i| i as u8);
+    let customization = b"custom-S";
+    let label = b"label";
+    let context = b"context";
+    let expected =
+        hex_literal::hex!("6775f883ac8a24456f84e761f05ecaf58f8f8dbc192a991cf4d6f25250ceffdc");
+
+    let derived = Counter::<'_, Hmac<Sha256>, OutputSize<U32>>::with_customization(customization)
+        .derive(&kin, true, true, label, context)
+        .unwrap();
+
+    assert_eq!(derived.as_slice(), expected);
+}